@@ -0,0 +1,130 @@
+//! A fuse that can hold one of two differently-typed futures.
+
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+pin_project! {
+    /// Fusing adapter that can hold a value produced by either of two
+    /// distinct future types, as long as they resolve to the same output.
+    ///
+    /// This is an allocation-free alternative to [Heap] when there are
+    /// exactly two known alternatives to fuse between, for example swapping
+    /// between a `time::Sleep` and a channel receive inside a single
+    /// [tokio::select] arm.
+    ///
+    /// Like [Stack] and [Heap], whichever branch is active is cleared once
+    /// it resolves, so the fuse reports empty afterwards and must be
+    /// re-armed with [set_left][EitherFuse::set_left] or
+    /// [set_right][EitherFuse::set_right] before it can resolve again.
+    ///
+    /// See [EitherFuse::new] for details.
+    ///
+    /// [Heap]: crate::Heap
+    /// [Stack]: crate::Stack
+    /// [tokio::select]: https://docs.rs/tokio/1/tokio/macro.select.html
+    #[project = EitherFuseProj]
+    pub struct EitherFuse<L, R> {
+        #[pin]
+        state: State<L, R>,
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    enum State<L, R> {
+        Left { #[pin] value: L },
+        Right { #[pin] value: R },
+        Empty,
+    }
+}
+
+impl<L, R> EitherFuse<L, R> {
+    /// Construct a new, empty fuse.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use async_fuse::EitherFuse;
+    /// use std::time::Duration;
+    /// use tokio::time;
+    /// use tokio::sync::mpsc;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let fuse = EitherFuse::<time::Sleep, _>::new();
+    /// tokio::pin!(fuse);
+    /// assert!(fuse.is_empty());
+    ///
+    /// fuse.as_mut().set_left(time::sleep(Duration::from_millis(1)));
+    /// (&mut fuse).await;
+    ///
+    /// // Resolving clears the fuse, so it must be re-armed before it can
+    /// // resolve again.
+    /// assert!(fuse.is_empty());
+    ///
+    /// let (_tx, mut rx) = mpsc::channel::<()>(1);
+    /// fuse.as_mut().set_right(async move {
+    ///     rx.recv().await;
+    /// });
+    /// assert!(!fuse.is_empty());
+    /// # }
+    /// ```
+    pub fn new() -> Self {
+        Self { state: State::Empty }
+    }
+
+    /// Fuse the left-hand future, dropping whatever was previously fused.
+    pub fn set_left(self: Pin<&mut Self>, left: L) {
+        self.project().state.set(State::Left { value: left });
+    }
+
+    /// Fuse the right-hand future, dropping whatever was previously fused.
+    pub fn set_right(self: Pin<&mut Self>, right: R) {
+        self.project().state.set(State::Right { value: right });
+    }
+
+    /// Clear the fused value.
+    ///
+    /// This will cause the old value to be dropped if present.
+    pub fn clear(self: Pin<&mut Self>) {
+        self.project().state.set(State::Empty);
+    }
+
+    /// Test if the fuse is currently empty.
+    pub fn is_empty(&self) -> bool {
+        matches!(self.state, State::Empty)
+    }
+}
+
+impl<L, R> Default for EitherFuse<L, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L, R, O> Future for EitherFuse<L, R>
+where
+    L: Future<Output = O>,
+    R: Future<Output = O>,
+{
+    type Output = O;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        let output = match this.state.as_mut().project() {
+            StateProj::Left { value } => value.poll(cx),
+            StateProj::Right { value } => value.poll(cx),
+            StateProj::Empty => return Poll::Pending,
+        };
+
+        if let Poll::Ready(output) = output {
+            this.state.set(State::Empty);
+            return Poll::Ready(output);
+        }
+
+        Poll::Pending
+    }
+}
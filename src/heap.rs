@@ -0,0 +1,344 @@
+//! A fuse that stores the future being fused on the heap, type erased.
+
+use std::alloc::{self, Layout};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::ptr::{self, NonNull};
+use std::task::{Context, Poll};
+
+#[cfg(feature = "cancellation-token")]
+use tokio_util::sync::CancellationToken;
+
+/// Fusing adapter that stores the future being fused on the heap, behind a
+/// type-erased pointer.
+///
+/// Unlike a plain `Option<Pin<Box<dyn Future<Output = O>>>>`, re-[set][Heap::set]ing
+/// this fuse with a future of the same size and alignment as the one
+/// currently stored reuses the existing allocation instead of deallocating
+/// and allocating again, which matters for hot re-arm loops like the
+/// [Stack] ticker example in [lib.rs](crate).
+///
+/// See [Heap::new] for details.
+///
+/// [Stack]: crate::Stack
+pub struct Heap<O> {
+    // Invariant: `value` is `None`, or `Some` pointing at a live, pinned
+    // `dyn Future<Output = O>` allocated with `layout`.
+    value: Option<NonNull<dyn Future<Output = O> + 'static>>,
+    layout: Layout,
+    #[cfg(feature = "cancellation-token")]
+    token: Option<CancellationToken>,
+}
+
+impl<O> Heap<O> {
+    /// Construct a new fuse out of the given future, boxed on the heap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokio::time;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let mut sleep = async_fuse::Heap::new(time::sleep(Duration::from_millis(100)));
+    /// (&mut sleep).await;
+    /// assert!(sleep.is_empty());
+    /// # }
+    /// ```
+    pub fn new<F>(value: F) -> Self
+    where
+        F: Future<Output = O> + 'static,
+    {
+        let mut this = Self {
+            value: None,
+            layout: Layout::new::<()>(),
+            #[cfg(feature = "cancellation-token")]
+            token: None,
+        };
+        this.set(value);
+        this
+    }
+
+    /// Construct a new fuse out of the given future, boxed on the heap, that
+    /// additionally clears itself the next time it is polled after `token`
+    /// is cancelled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+    /// use std::time::Duration;
+    /// use tokio::time;
+    /// use tokio_util::sync::CancellationToken;
+    ///
+    /// # fn noop_waker() -> Waker {
+    /// #     fn no_op(_: *const ()) {}
+    /// #     fn clone(_: *const ()) -> RawWaker {
+    /// #         RawWaker::new(std::ptr::null(), &VTABLE)
+    /// #     }
+    /// #     static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, no_op, no_op, no_op);
+    /// #     unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+    /// # }
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let waker = noop_waker();
+    /// let mut cx = Context::from_waker(&waker);
+    ///
+    /// let token = CancellationToken::new();
+    /// let mut sleep = async_fuse::Heap::new_until(time::sleep(Duration::from_millis(100)), token.clone());
+    ///
+    /// token.cancel();
+    ///
+    /// // Cancellation only clears the fuse the next time it's polled, so
+    /// // this still reports `Pending` rather than resolving.
+    /// assert_eq!(sleep.poll_immediate(&mut cx), Poll::Pending);
+    /// assert!(sleep.is_empty());
+    /// # }
+    /// ```
+    #[cfg(feature = "cancellation-token")]
+    pub fn new_until<F>(value: F, token: CancellationToken) -> Self
+    where
+        F: Future<Output = O> + 'static,
+    {
+        let mut this = Self {
+            value: None,
+            layout: Layout::new::<()>(),
+            token: Some(token),
+        };
+        this.set(value);
+        this
+    }
+
+    /// Set the fused value to be something else. The previous value will be
+    /// dropped.
+    ///
+    /// If `value` has the same size and alignment as the future currently
+    /// stored, the existing heap allocation is reused in place instead of
+    /// being freed and reallocated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokio::time;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let mut sleep = async_fuse::Heap::new(time::sleep(Duration::from_millis(100)));
+    /// sleep.set(time::sleep(Duration::from_millis(200)));
+    /// # }
+    /// ```
+    pub fn set<F>(&mut self, value: F)
+    where
+        F: Future<Output = O> + 'static,
+    {
+        let new_layout = Layout::new::<F>();
+
+        if let Some(ptr) = self.value {
+            if self.layout == new_layout {
+                let raw: *mut F = ptr.as_ptr().cast();
+
+                // SAFETY: `ptr` is live and was allocated with `self.layout`,
+                // which we just confirmed matches `new_layout`. Dropping
+                // goes through the original fat pointer, not `raw`, so the
+                // *old* value's vtable runs its destructor rather than
+                // `F`'s. The drop is caught so that, if it panics, `self`
+                // is left pointing at a live value before the unwind is
+                // resumed below, instead of a dangling one that would be
+                // double-dropped by `Heap::drop`.
+                let dropped = unsafe {
+                    panic::catch_unwind(AssertUnwindSafe(|| ptr::drop_in_place(ptr.as_ptr())))
+                };
+
+                // SAFETY: `raw` points at the same storage as `ptr`, which
+                // no longer holds a live value regardless of whether the
+                // drop above panicked, and fits `F` since `self.layout`
+                // matches `new_layout`.
+                unsafe { ptr::write(raw, value) };
+
+                // SAFETY: `raw` is non-null, since it came from `ptr`. The
+                // vtable pointer must be refreshed here even though the
+                // address didn't change: `F` may be a different concrete
+                // type than whatever was stored before, with a different
+                // vtable.
+                self.value = Some(unsafe {
+                    NonNull::new_unchecked(raw as *mut (dyn Future<Output = O> + 'static))
+                });
+
+                if let Err(payload) = dropped {
+                    panic::resume_unwind(payload);
+                }
+
+                return;
+            }
+        }
+
+        self.clear();
+
+        let raw: *mut F = if new_layout.size() == 0 {
+            NonNull::<F>::dangling().as_ptr()
+        } else {
+            // SAFETY: `new_layout` is non-zero sized, as checked above.
+            let raw = unsafe { alloc::alloc(new_layout) };
+
+            if raw.is_null() {
+                alloc::handle_alloc_error(new_layout);
+            }
+
+            raw.cast()
+        };
+
+        // SAFETY: `raw` is either dangling (for a zero-sized `F`) or was
+        // just allocated with `new_layout`, which is the layout of `F`.
+        unsafe { ptr::write(raw, value) };
+
+        // SAFETY: `raw` is non-null (dangling pointers are non-null).
+        let ptr = unsafe { NonNull::new_unchecked(raw as *mut (dyn Future<Output = O> + 'static)) };
+
+        self.value = Some(ptr);
+        self.layout = new_layout;
+    }
+
+    /// Clear the fused value.
+    ///
+    /// This will cause the old value to be dropped if present, freeing its
+    /// backing allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokio::time;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let mut sleep = async_fuse::Heap::new(time::sleep(Duration::from_millis(100)));
+    /// sleep.clear();
+    /// assert!(sleep.is_empty());
+    /// # }
+    /// ```
+    pub fn clear(&mut self) {
+        let Some(ptr) = self.value.take() else {
+            return;
+        };
+
+        // SAFETY: by the type invariant, `ptr` points to a live value
+        // allocated with `self.layout`.
+        unsafe {
+            ptr::drop_in_place(ptr.as_ptr());
+
+            if self.layout.size() != 0 {
+                alloc::dealloc(ptr.as_ptr().cast::<u8>(), self.layout);
+            }
+        }
+    }
+
+    /// Test if the polled for value is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokio::time;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let sleep = async_fuse::Heap::new(time::sleep(Duration::from_millis(100)));
+    /// assert!(!sleep.is_empty());
+    /// # }
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.value.is_none()
+    }
+
+    /// Poll the fused value exactly once, without parking indefinitely if
+    /// it isn't ready.
+    ///
+    /// Returns `Poll::Ready(None)` if the fuse is currently empty, so that
+    /// callers can tell "no value set" apart from "not ready yet", which is
+    /// reported as `Poll::Pending` same as with [Future::poll].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+    /// use std::time::Duration;
+    /// use tokio::time;
+    ///
+    /// # fn noop_waker() -> Waker {
+    /// #     fn no_op(_: *const ()) {}
+    /// #     fn clone(_: *const ()) -> RawWaker {
+    /// #         RawWaker::new(std::ptr::null(), &VTABLE)
+    /// #     }
+    /// #     static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, no_op, no_op, no_op);
+    /// #     unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+    /// # }
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let waker = noop_waker();
+    /// let mut cx = Context::from_waker(&waker);
+    ///
+    /// let mut sleep = async_fuse::Heap::new(time::sleep(Duration::from_secs(10)));
+    ///
+    /// // Hasn't elapsed yet, so this doesn't park indefinitely, it reports
+    /// // `Pending` instead.
+    /// assert_eq!(sleep.poll_immediate(&mut cx), Poll::Pending);
+    ///
+    /// // A cleared fuse reports `Ready(None)`, distinguishing "no value set"
+    /// // from "not ready yet".
+    /// sleep.clear();
+    /// assert_eq!(sleep.poll_immediate(&mut cx), Poll::Ready(None));
+    /// # }
+    /// ```
+    pub fn poll_immediate(&mut self, cx: &mut Context<'_>) -> Poll<Option<O>> {
+        if self.is_empty() {
+            return Poll::Ready(None);
+        }
+
+        match Pin::new(self).poll(cx) {
+            Poll::Ready(value) => Poll::Ready(Some(value)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn as_pin_mut(&mut self) -> Option<Pin<&mut (dyn Future<Output = O> + 'static)>> {
+        let mut ptr = self.value?;
+
+        // SAFETY: the value is heap allocated and never moved out of, so it
+        // is safely considered pinned for as long as it's stored in `self.value`.
+        Some(unsafe { Pin::new_unchecked(ptr.as_mut()) })
+    }
+}
+
+impl<O> Future for Heap<O> {
+    type Output = O;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        #[cfg(feature = "cancellation-token")]
+        if matches!(&self.token, Some(token) if token.is_cancelled()) {
+            self.clear();
+            return Poll::Pending;
+        }
+
+        match self.as_pin_mut() {
+            Some(value) => match value.poll(cx) {
+                Poll::Ready(output) => {
+                    self.clear();
+                    Poll::Ready(output)
+                }
+                Poll::Pending => Poll::Pending,
+            },
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<O> Drop for Heap<O> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
@@ -31,7 +31,7 @@
 //!
 //!     println!("tick");
 //!
-//!     sleep.set(async_fuse::Stack::new(time::sleep(Duration::from_millis(100))))
+//!     sleep.as_mut().set(time::sleep(Duration::from_millis(100)))
 //! }
 //! # }
 //! ```
@@ -41,8 +41,16 @@
 
 #![deny(missing_docs)]
 
+mod either;
 mod heap;
+mod poll_fn;
 mod stack;
+mod stream;
 
+pub use self::either::EitherFuse;
 pub use self::heap::Heap;
+pub use self::poll_fn::{poll_fn, PollFn};
+#[cfg(feature = "cancellation-token")]
+pub use self::poll_fn::poll_fn_until;
 pub use self::stack::Stack;
+pub use self::stream::{poll_stream, PollStream};
@@ -4,6 +4,9 @@ use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
+#[cfg(feature = "cancellation-token")]
+use tokio_util::sync::CancellationToken;
+
 /// Construct a fusing adapter that is capable of polling an interior value that
 /// is being polled using a custom function.
 ///
@@ -44,6 +47,66 @@ where
     PollFn {
         value: Some(value),
         poll,
+        #[cfg(feature = "cancellation-token")]
+        token: None,
+    }
+}
+
+/// Construct a [poll_fn] fuse that additionally clears itself the next time
+/// it is polled after `token` is cancelled.
+///
+/// This lets a select loop disarm an optional branch (a timer, a receiver,
+/// ...) off of a single shared shutdown signal, instead of threading manual
+/// [PollFn::clear] calls through every cancellation site.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+/// use std::time::Duration;
+/// use tokio::time;
+/// use tokio_util::sync::CancellationToken;
+///
+/// # fn noop_waker() -> Waker {
+/// #     fn no_op(_: *const ()) {}
+/// #     fn clone(_: *const ()) -> RawWaker {
+/// #         RawWaker::new(std::ptr::null(), &VTABLE)
+/// #     }
+/// #     static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, no_op, no_op, no_op);
+/// #     unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+/// # }
+/// #
+/// # #[tokio::main]
+/// # async fn main() {
+/// let waker = noop_waker();
+/// let mut cx = Context::from_waker(&waker);
+///
+/// let token = CancellationToken::new();
+/// let mut interval = async_fuse::poll_fn_until(
+///     time::interval(Duration::from_millis(200)),
+///     time::Interval::poll_tick,
+///     token.clone(),
+/// );
+///
+/// token.cancel();
+///
+/// // Cancellation only clears the fuse the next time it's polled, so this
+/// // still reports `Pending` rather than resolving.
+/// assert_eq!(interval.poll_immediate(&mut cx), Poll::Pending);
+/// assert!(interval.is_empty());
+/// # }
+/// ```
+#[cfg(feature = "cancellation-token")]
+pub fn poll_fn_until<T, P, O>(value: T, poll: P, token: CancellationToken) -> PollFn<T, P, O>
+where
+    T: Unpin,
+    P: Unpin,
+    P: FnMut(&mut T, &mut Context<'_>) -> Poll<O>,
+{
+    PollFn {
+        value: Some(value),
+        poll,
+        token: Some(token),
     }
 }
 
@@ -59,6 +122,8 @@ where
 {
     value: Option<T>,
     poll: P,
+    #[cfg(feature = "cancellation-token")]
+    token: Option<CancellationToken>,
 }
 
 impl<T, P, O> Future for PollFn<T, P, O>
@@ -72,6 +137,12 @@ where
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = &mut *self.as_mut();
 
+        #[cfg(feature = "cancellation-token")]
+        if matches!(&this.token, Some(token) if token.is_cancelled()) {
+            this.value = None;
+            return Poll::Pending;
+        }
+
         let inner = match this.value.as_mut() {
             Some(inner) => inner,
             None => return Poll::Pending,
@@ -157,4 +228,58 @@ where
     pub fn is_empty(&self) -> bool {
         self.value.is_none()
     }
+
+    /// Poll the fused value exactly once, without parking indefinitely if
+    /// it isn't ready.
+    ///
+    /// Returns `Poll::Ready(None)` if the fuse is currently empty, so that
+    /// callers can tell "no value set" apart from "not ready yet", which is
+    /// reported as `Poll::Pending` same as with [Future::poll].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::future::Future;
+    /// use std::pin::Pin;
+    /// use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+    /// use std::time::Duration;
+    /// use tokio::time;
+    ///
+    /// # fn noop_waker() -> Waker {
+    /// #     fn no_op(_: *const ()) {}
+    /// #     fn clone(_: *const ()) -> RawWaker {
+    /// #         RawWaker::new(std::ptr::null(), &VTABLE)
+    /// #     }
+    /// #     static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, no_op, no_op, no_op);
+    /// #     unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+    /// # }
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let waker = noop_waker();
+    /// let mut cx = Context::from_waker(&waker);
+    ///
+    /// // A freshly-armed fuse that hasn't elapsed yet reports `Pending`.
+    /// // `Sleep` isn't `Unpin`, so it's boxed to satisfy `poll_fn`'s bound.
+    /// let mut sleep = async_fuse::poll_fn(Box::pin(time::sleep(Duration::from_secs(10))), |sleep, cx| {
+    ///     sleep.as_mut().poll(cx)
+    /// });
+    /// assert_eq!(sleep.poll_immediate(&mut cx), Poll::Pending);
+    ///
+    /// // A cleared fuse reports `Ready(None)`, distinguishing "no value set"
+    /// // from "not ready yet".
+    /// sleep.clear();
+    /// assert_eq!(sleep.poll_immediate(&mut cx), Poll::Ready(None));
+    /// # }
+    /// ```
+    pub fn poll_immediate(&mut self, cx: &mut Context<'_>) -> Poll<Option<O>> {
+        if self.is_empty() {
+            return Poll::Ready(None);
+        }
+
+        match Pin::new(self).poll(cx) {
+            Poll::Ready(value) => Poll::Ready(Some(value)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
 }
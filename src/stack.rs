@@ -0,0 +1,248 @@
+//! A fuse that stores the future being fused directly, without boxing it.
+
+use pin_project_lite::pin_project;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+#[cfg(feature = "cancellation-token")]
+use tokio_util::sync::CancellationToken;
+
+// `pin_project!` only recognizes a bare `#[pin]` attribute on a field, so
+// the cancellation-token support can't be `#[cfg(...)]`-gated on the field
+// itself. Instead, the field is always present, and is a no-op `()` when
+// the feature is disabled.
+#[cfg(feature = "cancellation-token")]
+type Token = CancellationToken;
+#[cfg(not(feature = "cancellation-token"))]
+type Token = ();
+
+pin_project! {
+    /// Fusing adapter that stores the future being fused directly on the
+    /// stack.
+    ///
+    /// See [Stack::new] for details.
+    pub struct Stack<F> {
+        #[pin]
+        value: Option<F>,
+        token: Option<Token>,
+    }
+}
+
+impl<F> Stack<F> {
+    /// Construct a new fuse out of the given future.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokio::time;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let sleep = async_fuse::Stack::new(time::sleep(Duration::from_millis(100)));
+    /// tokio::pin!(sleep);
+    ///
+    /// (&mut sleep).await;
+    /// assert!(sleep.is_empty());
+    /// # }
+    /// ```
+    pub fn new(value: F) -> Self {
+        Self {
+            value: Some(value),
+            token: None,
+        }
+    }
+
+    /// Construct a new fuse out of the given future that additionally
+    /// clears itself the next time it is polled after `token` is cancelled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+    /// use std::time::Duration;
+    /// use tokio::time;
+    /// use tokio_util::sync::CancellationToken;
+    ///
+    /// # fn noop_waker() -> Waker {
+    /// #     fn no_op(_: *const ()) {}
+    /// #     fn clone(_: *const ()) -> RawWaker {
+    /// #         RawWaker::new(std::ptr::null(), &VTABLE)
+    /// #     }
+    /// #     static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, no_op, no_op, no_op);
+    /// #     unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+    /// # }
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let waker = noop_waker();
+    /// let mut cx = Context::from_waker(&waker);
+    ///
+    /// let token = CancellationToken::new();
+    /// let sleep = async_fuse::Stack::new_until(time::sleep(Duration::from_millis(100)), token.clone());
+    /// tokio::pin!(sleep);
+    ///
+    /// token.cancel();
+    ///
+    /// // Cancellation only clears the fuse the next time it's polled, so
+    /// // this still reports `Pending` rather than resolving.
+    /// assert_eq!(sleep.as_mut().poll_immediate(&mut cx), Poll::Pending);
+    /// assert!(sleep.is_empty());
+    /// # }
+    /// ```
+    #[cfg(feature = "cancellation-token")]
+    pub fn new_until(value: F, token: CancellationToken) -> Self {
+        Self {
+            value: Some(value),
+            token: Some(token),
+        }
+    }
+
+    /// Set the fused value to be something else. The previous value will be
+    /// dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokio::time;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let sleep = async_fuse::Stack::new(time::sleep(Duration::from_millis(100)));
+    /// tokio::pin!(sleep);
+    ///
+    /// sleep.as_mut().set(time::sleep(Duration::from_millis(200)));
+    /// # }
+    /// ```
+    pub fn set(self: Pin<&mut Self>, value: F) {
+        self.project().value.set(Some(value));
+    }
+
+    /// Clear the fused value.
+    ///
+    /// This will cause the old value to be dropped if present.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokio::time;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let sleep = async_fuse::Stack::new(time::sleep(Duration::from_millis(100)));
+    /// tokio::pin!(sleep);
+    ///
+    /// sleep.as_mut().clear();
+    /// assert!(sleep.is_empty());
+    /// # }
+    /// ```
+    pub fn clear(self: Pin<&mut Self>) {
+        self.project().value.set(None);
+    }
+
+    /// Test if the polled for value is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokio::time;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let sleep = async_fuse::Stack::new(time::sleep(Duration::from_millis(100)));
+    /// tokio::pin!(sleep);
+    ///
+    /// assert!(!sleep.is_empty());
+    /// # }
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.value.is_none()
+    }
+
+    /// Poll the fused value exactly once, without parking indefinitely if
+    /// it isn't ready.
+    ///
+    /// Returns `Poll::Ready(None)` if the fuse is currently empty, so that
+    /// callers can tell "no value set" apart from "not ready yet", which is
+    /// reported as `Poll::Pending` same as with [Future::poll].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+    /// use std::time::Duration;
+    /// use tokio::time;
+    ///
+    /// # fn noop_waker() -> Waker {
+    /// #     fn no_op(_: *const ()) {}
+    /// #     fn clone(_: *const ()) -> RawWaker {
+    /// #         RawWaker::new(std::ptr::null(), &VTABLE)
+    /// #     }
+    /// #     static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, no_op, no_op, no_op);
+    /// #     unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) }
+    /// # }
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let waker = noop_waker();
+    /// let mut cx = Context::from_waker(&waker);
+    ///
+    /// let sleep = async_fuse::Stack::new(time::sleep(Duration::from_secs(10)));
+    /// tokio::pin!(sleep);
+    ///
+    /// // Hasn't elapsed yet, so this doesn't park indefinitely, it reports
+    /// // `Pending` instead.
+    /// assert_eq!(sleep.as_mut().poll_immediate(&mut cx), Poll::Pending);
+    ///
+    /// // A cleared fuse reports `Ready(None)`, distinguishing "no value set"
+    /// // from "not ready yet".
+    /// sleep.as_mut().clear();
+    /// assert_eq!(sleep.as_mut().poll_immediate(&mut cx), Poll::Ready(None));
+    /// # }
+    /// ```
+    pub fn poll_immediate(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<F::Output>>
+    where
+        F: Future,
+    {
+        if self.is_empty() {
+            return Poll::Ready(None);
+        }
+
+        match self.poll(cx) {
+            Poll::Ready(value) => Poll::Ready(Some(value)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<F> Future for Stack<F>
+where
+    F: Future,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        #[cfg(feature = "cancellation-token")]
+        if matches!(this.token, Some(token) if token.is_cancelled()) {
+            this.value.set(None);
+            return Poll::Pending;
+        }
+
+        match this.value.as_mut().as_pin_mut() {
+            Some(value) => match value.poll(cx) {
+                Poll::Ready(output) => {
+                    this.value.set(None);
+                    Poll::Ready(output)
+                }
+                Poll::Pending => Poll::Pending,
+            },
+            None => Poll::Pending,
+        }
+    }
+}
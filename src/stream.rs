@@ -0,0 +1,135 @@
+//! Extension to poll a fused value repeatedly, like a stream.
+//!
+//! This is the stream-flavored counterpart to [poll_fn]: instead of
+//! yielding a single value per `.await`, it's expected that the
+//! underlying polling function keeps producing values (like
+//! [time::Interval::poll_tick] does) for as long as the fuse is populated.
+
+#[cfg(feature = "futures-core")]
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Construct a fusing adapter that can be polled repeatedly as a stream,
+/// using a custom polling function.
+///
+/// Unlike [poll_fn], the value produced by this adapter isn't consumed on
+/// `.await` in the usual one-value sense — calling [PollStream::next]
+/// repeatedly drives the same underlying value, and clearing it (through
+/// [PollStream::clear]) causes the stream to report [Poll::Pending] forever
+/// rather than terminating.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use tokio::time;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let mut interval = async_fuse::poll_stream(time::interval(Duration::from_millis(200)), time::Interval::poll_tick);
+///
+/// interval.next().await;
+/// assert!(!interval.is_empty());
+/// # }
+/// ```
+///
+/// [poll_fn]: crate::poll_fn
+/// [time::Interval::poll_tick]: https://docs.rs/tokio/1/tokio/time/struct.Interval.html#method.poll_tick
+pub fn poll_stream<T, P, O>(value: T, poll: P) -> PollStream<T, P, O>
+where
+    T: Unpin,
+    P: Unpin,
+    P: FnMut(&mut T, &mut Context<'_>) -> Poll<O>,
+{
+    PollStream {
+        value: Some(value),
+        poll,
+    }
+}
+
+/// Fusing adapter that can be polled repeatedly as a stream, using a custom
+/// polling function.
+///
+/// See [poll_stream] for details.
+pub struct PollStream<T, P, O>
+where
+    T: Unpin,
+    P: Unpin,
+    P: FnMut(&mut T, &mut Context<'_>) -> Poll<O>,
+{
+    value: Option<T>,
+    poll: P,
+}
+
+impl<T, P, O> PollStream<T, P, O>
+where
+    T: Unpin,
+    P: Unpin,
+    P: FnMut(&mut T, &mut Context<'_>) -> Poll<O>,
+{
+    /// Poll the next value out of this stream.
+    ///
+    /// If the fuse is empty, this returns [Poll::Pending] indefinitely
+    /// instead of terminating, matching the rest of the crate's "absent
+    /// means pending forever" contract.
+    pub fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<O> {
+        let inner = match self.value.as_mut() {
+            Some(inner) => inner,
+            None => return Poll::Pending,
+        };
+
+        (self.poll)(inner, cx)
+    }
+
+    /// Wait for the next value produced by this stream.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tokio::time;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let mut interval = async_fuse::poll_stream(time::interval(Duration::from_millis(200)), time::Interval::poll_tick);
+    ///
+    /// interval.next().await;
+    /// # }
+    /// ```
+    pub async fn next(&mut self) -> O {
+        std::future::poll_fn(|cx| self.poll_next(cx)).await
+    }
+
+    /// Set the fused value to be something else. The previous value will be
+    /// dropped.
+    pub fn set(&mut self, value: T) {
+        self.value = Some(value);
+    }
+
+    /// Clear the fused value.
+    ///
+    /// This will cause the old value to be dropped if present, and for this
+    /// stream to report [Poll::Pending] until a new value is [set][Self::set].
+    pub fn clear(&mut self) {
+        self.value = None;
+    }
+
+    /// Test if the polled for value is empty.
+    pub fn is_empty(&self) -> bool {
+        self.value.is_none()
+    }
+}
+
+#[cfg(feature = "futures-core")]
+impl<T, P, O> futures_core::Stream for PollStream<T, P, O>
+where
+    T: Unpin,
+    P: Unpin,
+    P: FnMut(&mut T, &mut Context<'_>) -> Poll<O>,
+{
+    type Item = O;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::into_inner(self).poll_next(cx).map(Some)
+    }
+}